@@ -1,9 +1,10 @@
 mod event;
 use crate::event::{Event, Events};
+use clap::Arg;
 use itertools::Itertools;
 use std::io;
 use std::time::Duration;
-use sysinfo::{ProcessExt, ProcessorExt, SystemExt};
+use sysinfo::{ComponentExt, DiskExt, NetworkExt, ProcessExt, ProcessorExt, SystemExt};
 use termion::event::Key;
 use termion::input::MouseTerminal;
 use termion::raw::IntoRawMode;
@@ -17,11 +18,95 @@ use tui::widgets::{
 use tui::{Frame, Terminal};
 
 // TODO: collect process-level history
+#[derive(Clone)]
 struct ProcessMeta {
     name: String,
-    cpu_usage: Vec<f32>,
+    cpu_usage: f32,
     memory: u64,
     count: usize,
+    pids: Vec<sysinfo::Pid>,
+}
+
+// Tracks the contents of the `/` search bar and its compiled regex, so the
+// (possibly expensive) compile only happens when the query actually changes.
+struct AppSearchState {
+    current_search_query: String,
+    current_cursor_position: usize,
+    current_regex: Option<Result<regex::Regex, regex::Error>>,
+}
+
+impl AppSearchState {
+    fn new() -> Self {
+        AppSearchState {
+            current_search_query: String::new(),
+            current_cursor_position: 0,
+            current_regex: None,
+        }
+    }
+
+    fn is_blank_search(&self) -> bool {
+        self.current_search_query.is_empty()
+    }
+
+    fn is_invalid_search(&self) -> bool {
+        matches!(self.current_regex, Some(Err(_)))
+    }
+
+    fn recompile(&mut self) {
+        self.current_regex = if self.is_blank_search() {
+            None
+        } else {
+            Some(regex::Regex::new(&self.current_search_query))
+        };
+    }
+
+    // `current_cursor_position` is a char index, not a byte index, so it stays
+    // valid across multi-byte characters; this maps it to the byte offset
+    // `String::insert`/`replace_range` actually need.
+    fn byte_index(&self) -> usize {
+        self.current_search_query
+            .char_indices()
+            .nth(self.current_cursor_position)
+            .map(|(i, _)| i)
+            .unwrap_or_else(|| self.current_search_query.len())
+    }
+
+    fn push_char(&mut self, c: char) {
+        let byte_index = self.byte_index();
+        self.current_search_query.insert(byte_index, c);
+        self.current_cursor_position += 1;
+        self.recompile();
+    }
+
+    fn backspace(&mut self) {
+        if self.current_cursor_position > 0 {
+            self.current_cursor_position -= 1;
+            let start = self.byte_index();
+            let end = self.current_search_query[start..]
+                .chars()
+                .next()
+                .map(|c| start + c.len_utf8())
+                .unwrap_or(start);
+            self.current_search_query.replace_range(start..end, "");
+            self.recompile();
+        }
+    }
+
+    fn move_left(&mut self) {
+        self.current_cursor_position = self.current_cursor_position.saturating_sub(1);
+    }
+
+    fn move_right(&mut self) {
+        if self.current_cursor_position < self.current_search_query.chars().count() {
+            self.current_cursor_position += 1;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.current_search_query.clear();
+        self.current_cursor_position = 0;
+        self.current_regex = None;
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -30,7 +115,88 @@ enum Sort {
     Memory,
 }
 
-fn get_processes(system: &sysinfo::System, sort: Sort) -> Vec<ProcessMeta> {
+struct DiskMeta {
+    mount_point: String,
+    used: u64,
+    total: u64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum TemperatureType {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl TemperatureType {
+    fn convert(self, celsius: f32) -> f32 {
+        match self {
+            TemperatureType::Celsius => celsius,
+            TemperatureType::Fahrenheit => celsius * 9f32 / 5f32 + 32f32,
+            TemperatureType::Kelvin => celsius + 273.15,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TemperatureType::Celsius => "C",
+            TemperatureType::Fahrenheit => "F",
+            TemperatureType::Kelvin => "K",
+        }
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    static UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024f64 && unit < UNITS.len() - 1 {
+        value /= 1024f64;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, UNITS[unit])
+}
+
+// The system-wide jiffy totals from `/proc/stat`: the sum of all fields on
+// the aggregate `cpu` line, and the idle+iowait columns.
+#[cfg(target_os = "linux")]
+fn read_proc_stat_jiffies() -> Option<(u64, u64)> {
+    let contents = std::fs::read_to_string("/proc/stat").ok()?;
+    let fields: Vec<u64> = contents
+        .lines()
+        .next()?
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|field| field.parse().ok())
+        .collect();
+    if fields.len() < 5 {
+        return None;
+    }
+    let total = fields.iter().sum();
+    let idle = fields[3] + fields[4];
+    Some((total, idle))
+}
+
+// utime (field 14) + stime (field 15) from `/proc/<pid>/stat`, skipping past
+// the `(comm)` field since it may itself contain spaces or parens.
+#[cfg(target_os = "linux")]
+fn read_proc_pid_jiffies(pid: sysinfo::Pid) -> Option<u64> {
+    let contents = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let after_comm = contents.rsplit(") ").next()?;
+    let fields: Vec<&str> = after_comm.split_whitespace().collect();
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+// On Linux, `cpu_overrides` carries the accurate per-pid percentage computed
+// from `/proc` jiffy deltas (see `App::compute_linux_cpu_usage`); elsewhere
+// it's always empty and we fall back to sysinfo's own estimate.
+fn get_processes(
+    system: &sysinfo::System,
+    sort: Sort,
+    cpu_overrides: &std::collections::HashMap<sysinfo::Pid, f32>,
+) -> Vec<ProcessMeta> {
     let mut processes = system
         .get_process_list()
         .values()
@@ -40,15 +206,21 @@ fn get_processes(system: &sysinfo::System, sort: Sort) -> Vec<ProcessMeta> {
         .map(|(name, group)| {
             let mut cpu_usage = 0f32;
             let mut memory = 0u64;
+            let mut pids = Vec::with_capacity(group.len());
             for process in &group {
-                cpu_usage += process.cpu_usage();
+                cpu_usage += cpu_overrides
+                    .get(&process.pid())
+                    .cloned()
+                    .unwrap_or_else(|| process.cpu_usage());
                 memory += process.memory();
+                pids.push(process.pid());
             }
             ProcessMeta {
                 name: name.to_owned(),
                 cpu_usage,
                 memory,
                 count: group.len(),
+                pids,
             }
         })
         .collect::<Vec<_>>();
@@ -56,11 +228,9 @@ fn get_processes(system: &sysinfo::System, sort: Sort) -> Vec<ProcessMeta> {
         Sort::Memory => processes.sort_by_key(|p| std::cmp::Reverse((p.memory) as u32)),
         Sort::Cpu => processes.sort_by_key(|p| std::cmp::Reverse((p.cpu_usage * 100f32) as u32)),
     };
+    // keep the full list here; truncating to a screen-sized page happens in
+    // App::filtered_processes, after the search regex has been applied
     processes
-        .into_iter()
-        //take enough for a reasonably large screen size
-        .take(100)
-        .collect()
 }
 
 fn draw_processes(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
@@ -69,12 +239,13 @@ fn draw_processes(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
         .title(" Process List ")
         .border_style(Style::default().fg(Color::Cyan))
         .borders(Borders::ALL);
-    let processes = app.processes.iter().map(
+    let processes = app.filtered_processes().into_iter().map(
         |ProcessMeta {
              name,
              cpu_usage,
              memory,
              count,
+             pids: _,
          }| {
             let style = match &app.selected {
                 Some(selected) if name == selected => {
@@ -122,34 +293,155 @@ fn draw_memory(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
         .render(&mut f, parent);
 }
 
-fn draw_cpu(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
-    let cpu = Block::default()
-        .title(" CPU Usage ")
+fn draw_disks(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
+    static HEADERS: [&str; 3] = [" Mount", "Used / Total", "Used %"];
+    let block = Block::default()
+        .title(" Disks ")
         .border_style(Style::default().fg(Color::Cyan))
         .borders(Borders::ALL);
+    let rows = app.disks.iter().map(|disk| {
+        let used_percent = if disk.total == 0 {
+            0f64
+        } else {
+            (disk.used as f64 / disk.total as f64) * 100f64
+        };
+        let data = vec![
+            disk.mount_point.clone(),
+            format!("{} / {}", format_bytes(disk.used), format_bytes(disk.total)),
+            format!("{:.1}", used_percent),
+        ];
+        Row::StyledData(data.into_iter(), Style::default())
+    });
+    Table::new(HEADERS.iter(), rows)
+        .header_style(Style::default().modifier(Modifier::BOLD))
+        .widths(&[
+            Constraint::Percentage(50),
+            Constraint::Percentage(30),
+            Constraint::Percentage(20),
+        ])
+        .block(block)
+        .render(&mut f, parent);
+}
 
-    let cpu_data = (if let Some(ref selected) = app.selected {
-        app.processes.iter().find_map(|p| {
-            if &p.name == selected {
-                Some(vec![p.cpu_usage as u64].into_iter().collect::<Vec<_>>())
-            } else {
-                None
-            }
-        })
-    } else {
-        None
-    })
-    .unwrap_or(app.cpu.iter().cloned().collect::<Vec<_>>());
+fn draw_network(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
+    let cols = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(parent);
 
+    let rx_block = Block::default()
+        .title(" Network RX ")
+        .border_style(Style::default().fg(Color::Cyan))
+        .borders(Borders::ALL);
+    let rx_data = app.network_rx.iter().cloned().collect::<Vec<_>>();
     Sparkline::default()
         .direction(RenderDirection::RTL)
-        .data(cpu_data.as_slice())
-        .style(Style::default().fg(Color::Red))
-        .max(100)
-        .block(cpu)
+        .data(rx_data.as_slice())
+        .style(Style::default().fg(Color::Green))
+        .block(rx_block)
+        .render(&mut f, cols[0]);
+
+    let tx_block = Block::default()
+        .title(" Network TX ")
+        .border_style(Style::default().fg(Color::Cyan))
+        .borders(Borders::ALL);
+    let tx_data = app.network_tx.iter().cloned().collect::<Vec<_>>();
+    Sparkline::default()
+        .direction(RenderDirection::RTL)
+        .data(tx_data.as_slice())
+        .style(Style::default().fg(Color::Magenta))
+        .block(tx_block)
+        .render(&mut f, cols[1]);
+}
+
+fn draw_temperature(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
+    static HEADERS: [&str; 2] = [" Sensor", "Temp"];
+    let block = Block::default()
+        .title(" Temperature ")
+        .border_style(Style::default().fg(Color::Cyan))
+        .borders(Borders::ALL);
+    let rows = app.temperatures.iter().map(|(label, celsius)| {
+        let data = vec![
+            label.clone(),
+            format!(
+                "{:.1}°{}",
+                app.temperature_type.convert(*celsius),
+                app.temperature_type.label()
+            ),
+        ];
+        Row::StyledData(data.into_iter(), Style::default())
+    });
+    Table::new(HEADERS.iter(), rows)
+        .header_style(Style::default().modifier(Modifier::BOLD))
+        .widths(&[Constraint::Percentage(60), Constraint::Percentage(40)])
+        .block(block)
         .render(&mut f, parent);
 }
 
+fn draw_cpu(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
+    if let Some(cpu_data) = app.selected.as_ref().and_then(|selected| {
+        app.processes
+            .iter()
+            .find(|p| &p.name == selected)
+            .map(|p| vec![p.cpu_usage as u64])
+    }) {
+        let cpu = Block::default()
+            .title(" CPU Usage ")
+            .border_style(Style::default().fg(Color::Cyan))
+            .borders(Borders::ALL);
+        Sparkline::default()
+            .direction(RenderDirection::RTL)
+            .data(cpu_data.as_slice())
+            .style(Style::default().fg(Color::Red))
+            .max(100)
+            .block(cpu)
+            .render(&mut f, parent);
+    } else if app.show_average_cpu {
+        let cpu = Block::default()
+            .title(" CPU Usage (avg) ")
+            .border_style(Style::default().fg(Color::Cyan))
+            .borders(Borders::ALL);
+        let cpu_data = app.cpu.iter().cloned().collect::<Vec<_>>();
+        Sparkline::default()
+            .direction(RenderDirection::RTL)
+            .data(cpu_data.as_slice())
+            .style(Style::default().fg(Color::Red))
+            .max(100)
+            .block(cpu)
+            .render(&mut f, parent);
+    } else {
+        draw_cpu_per_core(&mut f, app, parent);
+    }
+}
+
+fn draw_cpu_per_core(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
+    let core_count = app.cpu_per_core.len();
+    if core_count == 0 {
+        return;
+    }
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            vec![Constraint::Percentage((100 / core_count) as u16); core_count].as_ref(),
+        )
+        .split(parent);
+
+    for (i, history) in app.cpu_per_core.iter().enumerate() {
+        let block = Block::default()
+            .title(format!(" CPU {} ", i))
+            .border_style(Style::default().fg(Color::Cyan))
+            .borders(Borders::ALL);
+        let data = history.iter().cloned().collect::<Vec<_>>();
+        Sparkline::default()
+            .direction(RenderDirection::RTL)
+            .data(data.as_slice())
+            .style(Style::default().fg(Color::Red))
+            .max(100)
+            .block(block)
+            .render(&mut f, rows[i]);
+    }
+}
+
 fn draw_header(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
     let top = Layout::default()
         .direction(Direction::Horizontal)
@@ -169,6 +461,12 @@ fn draw_header(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
     if let Some(hostname) = &app.hostname {
         title.push(Text::raw(format!(" for {}", hostname)));
     }
+    if app.is_frozen {
+        title.push(Text::styled(
+            " [FROZEN]",
+            Style::default().fg(Color::Yellow).modifier(Modifier::BOLD),
+        ));
+    }
     Paragraph::new(title.iter()).render(&mut f, top[0]);
 
     if let Ok(load) = sys_info::loadavg() {
@@ -179,17 +477,120 @@ fn draw_header(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
         Block::default().title(&load).render(&mut f, top[1]);
     }
 
-    let date = chrono::Local::now();
-    let time = date.format("%H:%M:%S").to_string();
+    let time = match &app.frozen_time {
+        Some(frozen) => frozen.clone(),
+        None => chrono::Local::now().format("%H:%M:%S").to_string(),
+    };
 
     Paragraph::new([Text::raw(time)].iter())
         .alignment(Alignment::Right)
         .render(&mut f, top[2]);
 }
 
+// Carves a percentage-sized box out of the middle of `r`, for rendering
+// modal overlays on top of the main layout.
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_y) / 2),
+                Constraint::Percentage(percent_y),
+                Constraint::Percentage((100 - percent_y) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_x) / 2),
+                Constraint::Percentage(percent_x),
+                Constraint::Percentage((100 - percent_x) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(popup_layout[1])[1]
+}
+
+fn draw_confirm_delete(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
+    let processes = match &app.to_delete_process_list {
+        Some(processes) => processes,
+        None => return,
+    };
+    let area = centered_rect(50, 20, parent);
+    let block = Block::default()
+        .title(" Confirm Kill (dd) ")
+        .border_style(Style::default().fg(Color::Red))
+        .borders(Borders::ALL);
+
+    let pid_count: usize = processes.iter().map(|p| p.pids.len()).sum();
+    let names = processes
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut lines = vec![Text::raw(format!(
+        "Kill {} process{} ({})? [Enter] confirm  [Esc] cancel",
+        pid_count,
+        if pid_count == 1 { "" } else { "es" },
+        names,
+    ))];
+    if let Some(err) = &app.dd_err {
+        lines.push(Text::styled(err, Style::default().fg(Color::Red)));
+    }
+    Paragraph::new(lines.iter()).block(block).render(&mut f, area);
+}
+
+fn draw_help(mut f: &mut Frame<impl Backend>, parent: Rect) {
+    static KEYBINDINGS: [&str; 11] = [
+        "j / k        move the highlight down / up",
+        "Enter        select / deselect the highlighted process",
+        "/            open the search bar to filter by regex",
+        "Esc          close the search bar, or cancel a pending dialog",
+        "dd           kill every process in the highlighted group",
+        "Space / f    freeze / unfreeze live updates",
+        "a            toggle per-core CPU view / averaged view",
+        "m            sort the process list by memory",
+        "c            sort the process list by CPU",
+        "?            toggle this help overlay",
+        "q / Ctrl+c   quit itop",
+    ];
+
+    let area = centered_rect(60, 60, parent);
+    let block = Block::default()
+        .title(" Help (? to close) ")
+        .border_style(Style::default().fg(Color::Cyan))
+        .borders(Borders::ALL);
+    let lines = KEYBINDINGS.iter().map(|line| Text::raw(*line)).collect::<Vec<_>>();
+    Paragraph::new(lines.iter()).block(block).render(&mut f, area);
+}
+
+fn draw_search_bar(mut f: &mut Frame<impl Backend>, app: &App, parent: Rect) {
+    let search = Block::default()
+        .title(" Search ")
+        .border_style(Style::default().fg(Color::Cyan))
+        .borders(Borders::ALL);
+
+    let text = if app.search.is_invalid_search() {
+        vec![Text::styled(
+            format!("/{} (invalid regex)", app.search.current_search_query),
+            Style::default().fg(Color::Red),
+        )]
+    } else {
+        vec![Text::raw(format!("/{}", app.search.current_search_query))]
+    };
+
+    Paragraph::new(text.iter()).block(search).render(&mut f, parent);
+}
+
 struct App {
     memory: slice_deque::SliceDeque<u64>,
     cpu: slice_deque::SliceDeque<u64>,
+    cpu_per_core: Vec<slice_deque::SliceDeque<u64>>,
+    show_average_cpu: bool,
     processes: Vec<ProcessMeta>,
     system: sysinfo::System,
     title: String,
@@ -199,26 +600,160 @@ struct App {
     total_memory: u64,
     sort: Sort,
     wants_sort: Sort,
+    is_searching: bool,
+    search: AppSearchState,
+    awaiting_dd: bool,
+    to_delete_process_list: Option<Vec<ProcessMeta>>,
+    dd_err: Option<String>,
+    is_frozen: bool,
+    frozen_time: Option<String>,
+    disks: Vec<DiskMeta>,
+    network_rx: slice_deque::SliceDeque<u64>,
+    network_tx: slice_deque::SliceDeque<u64>,
+    prev_network_rx_total: u64,
+    prev_network_tx_total: u64,
+    has_network_totals: bool,
+    temperatures: Vec<(String, f32)>,
+    temperature_type: TemperatureType,
+    buffer_capacity: usize,
+    prev_process_jiffies: std::collections::HashMap<sysinfo::Pid, u64>,
+    prev_total_jiffies: u64,
+    has_cpu_jiffies_baseline: bool,
+    show_help: bool,
 }
 
-const BUFFER_CAPACITY: usize = 1000;
-
 impl App {
+    fn filtered_processes(&self) -> Vec<&ProcessMeta> {
+        let matching: Vec<&ProcessMeta> = match &self.search.current_regex {
+            Some(Ok(regex)) => self
+                .processes
+                .iter()
+                .filter(|p| regex.is_match(&p.name))
+                .collect(),
+            _ => self.processes.iter().collect(),
+        };
+        // take enough for a reasonably large screen size, after filtering so a
+        // match outside the top 100 by sort key is still found
+        matching.into_iter().take(100).collect()
+    }
+
+    fn confirm_delete(&mut self) {
+        let processes = match self.to_delete_process_list.take() {
+            Some(processes) => processes,
+            None => return,
+        };
+        let mut errors = Vec::new();
+        for meta in &processes {
+            for pid in &meta.pids {
+                match self.system.get_process(*pid) {
+                    Some(process) => {
+                        if !process.kill(sysinfo::Signal::Kill) {
+                            errors.push(format!("failed to kill {} ({})", meta.name, pid));
+                        }
+                    }
+                    None => errors.push(format!("{} ({}) no longer exists", meta.name, pid)),
+                }
+            }
+        }
+        self.dd_err = if errors.is_empty() {
+            None
+        } else {
+            // keep the dialog open so the failures are shown until Esc
+            self.to_delete_process_list = Some(processes);
+            Some(errors.join(", "))
+        };
+    }
+
     fn update(&mut self, processes: bool) {
         if processes {
             self.update_processes();
         }
         self.update_memory();
         self.update_cpu();
+        self.refresh_disks();
+        self.refresh_networks();
+        self.refresh_temperature();
+    }
+
+    fn refresh_disks(&mut self) {
+        self.system.refresh_disk_list();
+        self.system.refresh_disks();
+        self.disks = self
+            .system
+            .get_disks()
+            .iter()
+            .map(|disk| DiskMeta {
+                mount_point: disk.get_mount_point().to_string_lossy().into_owned(),
+                used: disk.get_total_space() - disk.get_available_space(),
+                total: disk.get_total_space(),
+            })
+            .collect();
+    }
+
+    fn refresh_networks(&mut self) {
+        self.system.refresh_networks_list();
+        self.system.refresh_networks();
+        let (rx_total, tx_total) = self.system.get_networks().iter().fold(
+            (0u64, 0u64),
+            |(rx, tx), (_, data)| (rx + data.get_received(), tx + data.get_transmitted()),
+        );
+
+        // seed the cumulative counters on the first tick rather than diffing
+        // against 0, which would push the entire since-boot total as one sample
+        if self.has_network_totals {
+            let rx_delta = rx_total.saturating_sub(self.prev_network_rx_total);
+            let tx_delta = tx_total.saturating_sub(self.prev_network_tx_total);
+
+            self.network_rx.push_front(rx_delta);
+            if self.network_rx.len() > self.buffer_capacity {
+                self.network_rx.pop_back();
+            }
+            self.network_tx.push_front(tx_delta);
+            if self.network_tx.len() > self.buffer_capacity {
+                self.network_tx.pop_back();
+            }
+        } else {
+            self.has_network_totals = true;
+        }
+
+        self.prev_network_rx_total = rx_total;
+        self.prev_network_tx_total = tx_total;
+    }
+
+    fn refresh_temperature(&mut self) {
+        self.system.refresh_components_list();
+        self.system.refresh_components();
+        self.temperatures = self
+            .system
+            .get_components_list()
+            .iter()
+            .map(|component| (component.get_label().to_owned(), component.get_temperature()))
+            .collect();
     }
 
     fn update_cpu(&mut self) {
         self.system.refresh_cpu();
         let processors = self.system.get_processor_list();
-        let total: f32 = processors.iter().map(|p| p.get_cpu_usage()).sum();
+        if self.cpu_per_core.len() != processors.len() {
+            self.cpu_per_core = processors
+                .iter()
+                .map(|_| slice_deque::SliceDeque::new())
+                .collect();
+        }
+
+        let mut total = 0f32;
+        for (history, processor) in self.cpu_per_core.iter_mut().zip(processors) {
+            let usage = processor.get_cpu_usage();
+            total += usage;
+            history.push_front((usage * 100f32) as u64);
+            if history.len() > self.buffer_capacity {
+                history.pop_back();
+            }
+        }
+
         let cpu_percentage = (total / (processors.len() as f32) * 100f32) as u64;
         self.cpu.push_front(cpu_percentage);
-        if self.cpu.len() > BUFFER_CAPACITY {
+        if self.cpu.len() > self.buffer_capacity {
             self.cpu.pop_back();
         }
     }
@@ -229,32 +764,170 @@ impl App {
         let total = self.system.get_total_memory() as f64;
         let memory_percentage = (used / total * 100f64) as u64;
         self.memory.push_front(memory_percentage);
-        if self.memory.len() > BUFFER_CAPACITY {
+        if self.memory.len() > self.buffer_capacity {
             self.memory.pop_back();
         }
     }
 
     fn update_processes(&mut self) {
         self.system.refresh_processes();
-        let processes = get_processes(&self.system, self.sort);
+        let cpu_overrides = self.compute_linux_cpu_usage();
+        let processes = get_processes(&self.system, self.sort, &cpu_overrides);
         self.total_memory = self.system.get_total_memory();
         std::mem::replace(&mut self.processes, processes);
     }
+
+    #[cfg(target_os = "linux")]
+    fn compute_linux_cpu_usage(&mut self) -> std::collections::HashMap<sysinfo::Pid, f32> {
+        let mut result = std::collections::HashMap::new();
+        let (total, _idle) = match read_proc_stat_jiffies() {
+            Some(totals) => totals,
+            None => return result,
+        };
+
+        // seed the baseline on the first tick rather than diffing against the
+        // literal 0 `prev_total_jiffies` starts at, which would otherwise read
+        // as the entire since-boot jiffy count
+        if !self.has_cpu_jiffies_baseline {
+            self.has_cpu_jiffies_baseline = true;
+            self.prev_total_jiffies = total;
+            self.prev_process_jiffies = self
+                .system
+                .get_process_list()
+                .keys()
+                .filter_map(|pid| read_proc_pid_jiffies(*pid).map(|jiffies| (*pid, jiffies)))
+                .collect();
+            return result;
+        }
+
+        let total_delta = total.saturating_sub(self.prev_total_jiffies);
+        self.prev_total_jiffies = total;
+        if total_delta == 0 {
+            return result;
+        }
+
+        let num_cores = self.cpu_per_core.len().max(1) as f32;
+        let mut current_jiffies = std::collections::HashMap::new();
+        for pid in self.system.get_process_list().keys() {
+            if let Some(jiffies) = read_proc_pid_jiffies(*pid) {
+                let prev = self.prev_process_jiffies.get(pid).cloned().unwrap_or(jiffies);
+                let process_delta = jiffies.saturating_sub(prev);
+                let cpu_percent = (process_delta as f32 / total_delta as f32) * num_cores * 100f32;
+                result.insert(*pid, cpu_percent);
+                current_jiffies.insert(*pid, jiffies);
+            }
+        }
+        self.prev_process_jiffies = current_jiffies;
+        result
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn compute_linux_cpu_usage(&mut self) -> std::collections::HashMap<sysinfo::Pid, f32> {
+        std::collections::HashMap::new()
+    }
+}
+
+struct CliConfig {
+    tick_rate: Duration,
+    buffer_capacity: usize,
+    default_sort: Sort,
+    average_cpu: bool,
+    temperature_type: TemperatureType,
+}
+
+fn parse_args() -> CliConfig {
+    let matches = clap::App::new("itop")
+        .about("A terminal resource monitor")
+        .arg(
+            Arg::with_name("rate")
+                .long("rate")
+                .value_name("MS")
+                .help("Event tick rate in milliseconds")
+                .takes_value(true)
+                .default_value("300"),
+        )
+        .arg(
+            Arg::with_name("buffer-capacity")
+                .long("buffer-capacity")
+                .value_name("N")
+                .help("Number of samples retained for each sparkline history")
+                .takes_value(true)
+                .default_value("1000"),
+        )
+        .arg(
+            Arg::with_name("default-sort")
+                .long("default-sort")
+                .value_name("SORT")
+                .possible_values(&["cpu", "memory"])
+                .help("Column the process list is sorted by on startup")
+                .takes_value(true)
+                .default_value("cpu"),
+        )
+        .arg(
+            Arg::with_name("average-cpu")
+                .long("average-cpu")
+                .help("Start with the aggregate CPU sparkline instead of one per core"),
+        )
+        .arg(
+            Arg::with_name("temperature-type")
+                .long("temperature-type")
+                .value_name("UNIT")
+                .possible_values(&["c", "f", "k"])
+                .help("Unit to display sensor temperatures in")
+                .takes_value(true)
+                .default_value("c"),
+        )
+        .get_matches();
+
+    let tick_rate = Duration::from_millis(
+        matches
+            .value_of("rate")
+            .unwrap()
+            .parse()
+            .unwrap_or(300),
+    );
+    let buffer_capacity = matches
+        .value_of("buffer-capacity")
+        .unwrap()
+        .parse()
+        .unwrap_or(1000);
+    let default_sort = match matches.value_of("default-sort").unwrap() {
+        "memory" => Sort::Memory,
+        _ => Sort::Cpu,
+    };
+    let average_cpu = matches.is_present("average-cpu");
+    let temperature_type = match matches.value_of("temperature-type").unwrap() {
+        "f" => TemperatureType::Fahrenheit,
+        "k" => TemperatureType::Kelvin,
+        _ => TemperatureType::Celsius,
+    };
+
+    CliConfig {
+        tick_rate,
+        buffer_capacity,
+        default_sort,
+        average_cpu,
+        temperature_type,
+    }
 }
 
 fn main() -> Result<(), failure::Error> {
+    let config = parse_args();
+
     let stdout = io::stdout().into_raw_mode()?;
     let stdout = MouseTerminal::from(stdout);
     let stdout = AlternateScreen::from(stdout);
     let backend = TermionBackend::new(stdout);
     let events = Events::with_config(event::Config {
         exit_key: Key::Char('q'),
-        tick_rate: Duration::from_millis(300),
+        tick_rate: config.tick_rate,
     });
     let mut terminal = Terminal::new(backend)?;
     let mut app = App {
         memory: slice_deque::SliceDeque::new(),
         cpu: slice_deque::SliceDeque::new(),
+        cpu_per_core: vec![],
+        show_average_cpu: config.average_cpu,
         processes: vec![],
         system: sysinfo::System::new(),
         hostname: sys_info::hostname().ok(),
@@ -262,24 +935,61 @@ fn main() -> Result<(), failure::Error> {
         highlighted: None,
         selected: None,
         total_memory: 0u64,
-        sort: Sort::Cpu,
-        wants_sort: Sort::Cpu,
+        sort: config.default_sort,
+        wants_sort: config.default_sort,
+        is_searching: false,
+        search: AppSearchState::new(),
+        awaiting_dd: false,
+        to_delete_process_list: None,
+        dd_err: None,
+        is_frozen: false,
+        frozen_time: None,
+        disks: vec![],
+        network_rx: slice_deque::SliceDeque::new(),
+        network_tx: slice_deque::SliceDeque::new(),
+        prev_network_rx_total: 0,
+        prev_network_tx_total: 0,
+        has_network_totals: false,
+        temperatures: vec![],
+        temperature_type: config.temperature_type,
+        buffer_capacity: config.buffer_capacity,
+        prev_process_jiffies: std::collections::HashMap::new(),
+        prev_total_jiffies: 0,
+        has_cpu_jiffies_baseline: false,
+        show_help: false,
     };
 
     let mut i = 0;
     loop {
         terminal.draw(|mut f| {
-            let outer = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints(
-                    [
-                        Constraint::Percentage(5),
-                        Constraint::Percentage(47),
-                        Constraint::Percentage(47),
-                    ]
-                    .as_ref(),
-                )
-                .split(f.size());
+            let outer = if app.is_searching {
+                Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(
+                        [
+                            Constraint::Percentage(5),
+                            Constraint::Percentage(30),
+                            Constraint::Percentage(30),
+                            Constraint::Percentage(30),
+                            Constraint::Length(3),
+                        ]
+                        .as_ref(),
+                    )
+                    .split(f.size())
+            } else {
+                Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(
+                        [
+                            Constraint::Percentage(5),
+                            Constraint::Percentage(32),
+                            Constraint::Percentage(32),
+                            Constraint::Percentage(31),
+                        ]
+                        .as_ref(),
+                    )
+                    .split(f.size())
+            };
 
             draw_header(&mut f, &app, outer[0]);
             draw_cpu(&mut f, &app, outer[1]);
@@ -291,14 +1001,98 @@ fn main() -> Result<(), failure::Error> {
 
             draw_memory(&mut f, &app, bottom[0]);
             draw_processes(&mut f, &app, bottom[1]);
+
+            let extra = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(34),
+                        Constraint::Percentage(33),
+                        Constraint::Percentage(33),
+                    ]
+                    .as_ref(),
+                )
+                .split(outer[3]);
+
+            draw_disks(&mut f, &app, extra[0]);
+            draw_network(&mut f, &app, extra[1]);
+            draw_temperature(&mut f, &app, extra[2]);
+
+            if app.is_searching {
+                draw_search_bar(&mut f, &app, outer[4]);
+            }
+
+            if app.to_delete_process_list.is_some() {
+                draw_confirm_delete(&mut f, &app, f.size());
+            }
+
+            if app.show_help {
+                draw_help(&mut f, f.size());
+            }
         })?;
         match events.next()? {
+            Event::Input(k) if app.to_delete_process_list.is_some() => match k {
+                Key::Char('\n') => app.confirm_delete(),
+                Key::Esc => {
+                    app.to_delete_process_list = None;
+                    app.dd_err = None;
+                }
+                _ => (),
+            },
+            Event::Input(k) if app.show_help => match k {
+                Key::Esc | Key::Char('?') => app.show_help = false,
+                _ => (),
+            },
+            // Up/Down fall through to the navigation arms below even while the
+            // search box has focus, so the highlight can move over the filtered
+            // list without losing the in-progress query; letters stay here since
+            // the query itself may contain them (e.g. `j`/`k`).
+            Event::Input(k) if app.is_searching && k != Key::Up && k != Key::Down => match k {
+                Key::Esc => {
+                    // only discard the filter if there was nothing typed; a
+                    // non-blank query keeps filtering after the box closes
+                    if app.search.is_blank_search() {
+                        app.search.reset();
+                    }
+                    app.is_searching = false;
+                }
+                Key::Char('\n') => {
+                    app.is_searching = false;
+                }
+                Key::Backspace => app.search.backspace(),
+                Key::Left => app.search.move_left(),
+                Key::Right => app.search.move_right(),
+                Key::Char(c) => app.search.push_char(c),
+                _ => (),
+            },
+            Event::Input(Key::Char('?')) => {
+                app.show_help = true;
+                app.awaiting_dd = false;
+            }
+            Event::Input(Key::Char('/')) => {
+                app.is_searching = true;
+                app.awaiting_dd = false;
+            }
+            Event::Input(Key::Char('d')) => {
+                if app.awaiting_dd {
+                    app.awaiting_dd = false;
+                    if let Some(highlighted) = &app.highlighted {
+                        if let Some(group) = app.processes.iter().find(|p| &p.name == highlighted)
+                        {
+                            app.to_delete_process_list = Some(vec![group.clone()]);
+                        }
+                    }
+                } else {
+                    app.awaiting_dd = true;
+                }
+            }
             Event::Input(k) if k == Key::Up || k == Key::Char('k') => {
+                app.awaiting_dd = false;
                 // comparing with 0 instead of decrementing first to avoid overflow
                 if let Some(highlighted) = &app.highlighted {
                     if let Some(process) = app
-                        .processes
-                        .iter()
+                        .filtered_processes()
+                        .into_iter()
                         .rev()
                         .skip_while(|&p| p.name != *highlighted)
                         .skip(1)
@@ -312,10 +1106,11 @@ fn main() -> Result<(), failure::Error> {
                 }
             }
             Event::Input(k) if k == Key::Down || k == Key::Char('j') => {
+                app.awaiting_dd = false;
                 if let Some(highlighted) = &app.highlighted {
                     if let Some(process) = app
-                        .processes
-                        .iter()
+                        .filtered_processes()
+                        .into_iter()
                         .skip_while(|&p| p.name != *highlighted)
                         .skip(1)
                         .next()
@@ -326,10 +1121,15 @@ fn main() -> Result<(), failure::Error> {
                         app.highlighted = None;
                     }
                 } else {
-                    app.highlighted = app.processes.iter().next().map(|p| p.name.to_owned());
+                    app.highlighted = app
+                        .filtered_processes()
+                        .into_iter()
+                        .next()
+                        .map(|p| p.name.to_owned());
                 }
             }
             Event::Input(Key::Char('\n')) => {
+                app.awaiting_dd = false;
                 match (&app.highlighted, &app.selected) {
                     (Some(highlighted), Some(selected)) if highlighted == selected => {
                         app.selected = None
@@ -341,26 +1141,44 @@ fn main() -> Result<(), failure::Error> {
                     _ => (),
                 };
             }
+            Event::Input(k) if k == Key::Char('a') => {
+                app.awaiting_dd = false;
+                app.show_average_cpu = !app.show_average_cpu;
+            }
+            Event::Input(k) if k == Key::Char(' ') || k == Key::Char('f') => {
+                app.awaiting_dd = false;
+                app.is_frozen = !app.is_frozen;
+                app.frozen_time = if app.is_frozen {
+                    Some(chrono::Local::now().format("%H:%M:%S").to_string())
+                } else {
+                    None
+                };
+            }
             Event::Input(k) if k == Key::Char('m') => {
+                app.awaiting_dd = false;
                 app.wants_sort = Sort::Memory;
             }
             Event::Input(k) if k == Key::Char('c') => {
+                app.awaiting_dd = false;
                 app.wants_sort = Sort::Cpu;
             }
             Event::Input(input) => {
+                app.awaiting_dd = false;
                 if input == Key::Ctrl('c') || input == Key::Char('q') {
                     break;
                 }
             }
             Event::Tick => {
-                // refreshing processes is expensive, so do it less frequently
-                let sort_updated = app.sort != app.wants_sort;
-                let update_processes = i % 8 == 0 || sort_updated;
-                if sort_updated {
-                    app.sort = app.wants_sort;
+                if !app.is_frozen {
+                    // refreshing processes is expensive, so do it less frequently
+                    let sort_updated = app.sort != app.wants_sort;
+                    let update_processes = i % 8 == 0 || sort_updated;
+                    if sort_updated {
+                        app.sort = app.wants_sort;
+                    }
+                    app.update(update_processes);
+                    i += 1;
                 }
-                app.update(update_processes);
-                i += 1;
             }
         }
     }